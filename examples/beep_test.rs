@@ -8,12 +8,14 @@
 //! - The audio node processor: This type does the audio processing. It is running entirely separate from
 //!   Bevy, so any changes need to be synchronized.
 //!
-//! In this example, we use shared atomics as a means of communicating the parameters between Bevy and the audio engine.
-//! There are different solutions available, this is the simplest one to set up.
-use atomic_float::AtomicF32;
+//! In this example, synchronization goes through the control channel that `NodePlugin` sets up for
+//! every `NodeComponent`: `Beep` describes its `Params` snapshot, and the processor drains it at the
+//! start of every `process` call.
+use atomic_float::{AtomicF32, AtomicF64};
 use bevy::prelude::Val::Px;
 use bevy::prelude::*;
-use bevy_audio_v2::node::{NodeComponent, NodePlugin};
+use bevy_audio_v2::node::{ControlMessage, ControlReceiver, NodeComponent, NodePlugin, StopMode};
+use bevy_audio_v2::param::AudioParam;
 use bevy_audio_v2::{AudioGraph, AudioPlugin};
 use firewheel::graph::NodeID;
 use firewheel::node::{AudioNode, AudioNodeInfo, AudioNodeProcessor, ProcInfo};
@@ -23,15 +25,24 @@ use std::f32::consts::TAU;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
-#[derive(Debug)]
-struct BeepNodeImpl {
-    amplitude: AtomicF32,
-    frequency: AtomicF32,
+/// Parameters for a [`BeepNode`], sent across the control channel whenever [`Beep`] changes.
+///
+/// `frequency` carries its own automation schedule so it can be ramped glitch-free instead of
+/// snapped; `amplitude` is still just toggled on/off so it stays a plain value.
+#[derive(Debug, Clone)]
+struct BeepParams {
+    amplitude: f32,
+    frequency: AudioParam,
 }
 
 /// Audio node type.
-#[derive(Debug, Clone, Deref, Component)]
-struct BeepNode(Arc<BeepNodeImpl>);
+#[derive(Debug, Component)]
+struct BeepNode {
+    params: BeepParams,
+    receiver: crossbeam_channel::Receiver<ControlMessage<BeepParams>>,
+    fade_gain: Arc<AtomicF32>,
+    clock: Arc<AtomicF64>,
+}
 
 impl<C, const MBF: usize> AudioNode<C, MBF> for BeepNode {
     fn debug_name(&self) -> &'static str {
@@ -54,16 +65,22 @@ impl<C, const MBF: usize> AudioNode<C, MBF> for BeepNode {
         _num_outputs: usize,
     ) -> Result<Box<dyn AudioNodeProcessor<C, MBF>>, Box<dyn Error>> {
         Ok(Box::new(BeepNodeProcessor {
-            params: self.clone(),
-            discretization_factor: TAU / sample_rate as f32,
+            params: self.params.clone(),
+            receiver: self.receiver.clone(),
+            fade_gain: self.fade_gain.clone(),
+            clock: self.clock.clone(),
+            sample_rate: sample_rate as f32,
             phase: 0.,
         }))
     }
 }
 
 struct BeepNodeProcessor {
-    params: BeepNode,
-    discretization_factor: f32,
+    params: BeepParams,
+    receiver: crossbeam_channel::Receiver<ControlMessage<BeepParams>>,
+    fade_gain: Arc<AtomicF32>,
+    clock: Arc<AtomicF64>,
+    sample_rate: f32,
     phase: f32,
 }
 
@@ -73,32 +90,60 @@ impl<C, const MBF: usize> AudioNodeProcessor<C, MBF> for BeepNodeProcessor {
         frames: BlockFrames<MBF>,
         _inputs: &[&[f32; MBF]],
         outputs: &mut [&mut [f32; MBF]],
-        _proc_info: ProcInfo<C>,
+        proc_info: ProcInfo<C>,
     ) {
-        let step = self.params.frequency.load(Ordering::Relaxed) * self.discretization_factor;
-        let amplitude = self.params.amplitude.load(Ordering::Relaxed);
+        while let Ok(ControlMessage::Update(params)) = self.receiver.try_recv() {
+            self.params = params;
+        }
+
+        // `AudioParam` ramps are timed against this same clock (see `toggle_beep`), so a ramp
+        // scheduled from the Bevy side lands on the sample it was meant to, however long the node
+        // has already been running.
+        let mut time = proc_info.clock_samples as f64 / self.sample_rate as f64;
+        let amplitude = self.params.amplitude * self.fade_gain.load(Ordering::Relaxed);
         for i in 0..frames.get() {
+            let frequency = self.params.frequency.tick(time);
             outputs[0][i] = self.phase.sin() * amplitude;
-            self.phase += step;
+            self.phase += frequency * TAU / self.sample_rate;
+            time += 1. / self.sample_rate as f64;
         }
+        self.clock.store(time, Ordering::Relaxed);
     }
 }
 
-#[derive(Debug, Copy, Clone, Component)]
+#[derive(Debug, Component)]
 struct Beep {
     amplitude: f32,
-    frequency: f32,
+    frequency: AudioParam,
 }
 
+/// Lets [`Beep::fade_out`] silence the processor without going through the control channel, since
+/// the channel's sending end is already torn down by the time a node starts fading out.
+#[derive(Debug, Clone, Component)]
+struct BeepFadeGain(Arc<AtomicF32>);
+
+/// Mirrors the processor's transport clock (in seconds) back onto the entity, so `toggle_beep` can
+/// anchor newly scheduled ramps against the time the processor is actually at instead of a
+/// `frequency`-local clock that starts from zero at activation.
+#[derive(Debug, Clone, Component)]
+struct BeepClock(Arc<AtomicF64>);
+
 impl NodeComponent for Beep {
+    type Params = BeepParams;
+    type FadeHandle = Arc<AtomicF32>;
+
     fn create_node(mut entity: EntityWorldMut, audio_graph: &mut AudioGraph) -> NodeID {
         let this = entity.get::<Beep>().unwrap();
-        let node = BeepNode(Arc::new(BeepNodeImpl {
-            amplitude: AtomicF32::new(this.amplitude),
-            frequency: AtomicF32::new(this.frequency),
-        }));
-        entity.insert(node.clone());
-        let node: Box<dyn AudioNode<_, 512>> = Box::new(node.clone());
+        let params = this.to_params();
+        let receiver = entity.get::<ControlReceiver<Self>>().unwrap().0.clone();
+        let fade_gain = Arc::new(AtomicF32::new(1.));
+        let clock = Arc::new(AtomicF64::new(0.));
+        let node: Box<dyn AudioNode<_, 512>> = Box::new(BeepNode {
+            params,
+            receiver,
+            fade_gain: fade_gain.clone(),
+            clock: clock.clone(),
+        });
         let node = audio_graph.add_node(0, 1, node);
         audio_graph
             .connect(node, 0, audio_graph.graph_out_node(), 0, false)
@@ -106,15 +151,27 @@ impl NodeComponent for Beep {
         audio_graph
             .connect(node, 0, audio_graph.graph_out_node(), 1, false)
             .unwrap();
+        entity.insert((BeepFadeGain(fade_gain), BeepClock(clock)));
         node
     }
-}
 
-fn on_change_beep(q: Query<(&Beep, &BeepNode), Changed<Beep>>) {
-    for (beep, node) in &q {
-        info!("Beep changed: amplitude = {}, frequency = {}", beep.amplitude, beep.frequency);
-        node.amplitude.store(beep.amplitude, Ordering::Relaxed);
-        node.frequency.store(beep.frequency, Ordering::Relaxed);
+    fn to_params(&self) -> Self::Params {
+        BeepParams {
+            amplitude: self.amplitude,
+            frequency: self.frequency.clone(),
+        }
+    }
+
+    fn stop_mode() -> StopMode {
+        StopMode::AllowFadeout { duration: 0.3 }
+    }
+
+    fn fade_handle(entity: EntityRef) -> Self::FadeHandle {
+        entity.get::<BeepFadeGain>().unwrap().0.clone()
+    }
+
+    fn fade_out(fade_gain: &Self::FadeHandle, _audio_graph: &mut AudioGraph, _node_id: NodeID, progress: f32) {
+        fade_gain.store(1. - progress, Ordering::Relaxed);
     }
 }
 
@@ -123,22 +180,38 @@ fn main() {
         .add_plugins((DefaultPlugins, AudioPlugin, NodePlugin::<Beep>::default()))
         .add_systems(Startup, (setup_beep, setup_ui))
         .add_systems(Update, toggle_beep)
-        .add_systems(PostUpdate, (on_change_beep, handle_ui_changes.run_if(|q: Query<(), Changed<Beep>>| !q.is_empty())))
+        .add_systems(PostUpdate, handle_ui_changes.run_if(|q: Query<(), Changed<Beep>>| !q.is_empty()))
         .run();
 }
 
 fn setup_beep(mut commands: Commands) {
-    commands.spawn((Beep { amplitude: 0., frequency: 440. }, ActiveEntityMarker));
+    commands.spawn((
+        Beep {
+            amplitude: 0.,
+            frequency: AudioParam::new(440.),
+        },
+        ActiveEntityMarker,
+    ));
 }
 
 fn toggle_beep(
-    mut q: Query<&mut Beep, With<ActiveEntityMarker>>,
+    mut q: Query<(&mut Beep, &BeepClock), With<ActiveEntityMarker>>,
     keyboard: Res<ButtonInput<KeyCode>>,
 ) {
+    let Ok((mut beep, clock)) = q.get_single_mut() else {
+        return;
+    };
     if keyboard.just_pressed(KeyCode::Space) {
-        let mut beep = q.single_mut();
         beep.amplitude = if beep.amplitude > f32::EPSILON { 0. } else { 1. };
     }
+    if keyboard.just_pressed(KeyCode::ArrowUp) || keyboard.just_pressed(KeyCode::ArrowDown) {
+        // Catch the schedule up to the processor's actual clock before adding a ramp, so `time`
+        // below lands where the processor will be when it receives this update instead of wherever
+        // the node's automation clock last left off (zero, if nothing has been scheduled yet).
+        beep.frequency.sync_schedule_time(clock.0.load(Ordering::Relaxed));
+        let target = if keyboard.just_pressed(KeyCode::ArrowUp) { 880. } else { 440. };
+        beep.frequency.linear_ramp_to(target, 1.);
+    }
 }
 
 #[derive(Component)]
@@ -0,0 +1,199 @@
+//! Shared effect buses with aux sends, echoing OpenAL's `AuxEffectSlot`/EFX model: several source
+//! nodes can feed a single reverb (or other effect) instance instead of each instantiating their
+//! own, and the per-source send level is just another gain stage reusing the routing pattern
+//! established by [`crate::spatial`].
+use crate::node::{NodeComponent, NodePlugin};
+use crate::{AudioGraph, BevyContext, NodeId, UpdateAudioGraphExt};
+use atomic_float::AtomicF32;
+use bevy_app::{App, Plugin, PostUpdate};
+use bevy_ecs::prelude::*;
+use bevy_ecs::world::EntityWorldMut;
+use bevy_utils::EntityHashMap;
+use firewheel::graph::NodeID;
+use firewheel::node::{AudioNode, AudioNodeInfo, AudioNodeProcessor, ProcInfo};
+use firewheel::BlockFrames;
+use std::error::Error;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// Registers a shared effect node (e.g. a reverb) in the [`AudioGraph`], wired straight to the
+/// graph output. Other nodes reach it through an [`EffectSend`] rather than connecting directly.
+///
+/// The effect's DSP itself isn't this crate's concern: `EffectBus` just needs a factory that
+/// builds the boxed [`AudioNode`] once, the same way any other [`NodeComponent`] would.
+#[derive(Component, Clone)]
+pub struct EffectBus {
+    factory: Arc<dyn Fn() -> Box<dyn AudioNode<BevyContext, 512>> + Send + Sync>,
+}
+
+impl EffectBus {
+    pub fn new(factory: impl Fn() -> Box<dyn AudioNode<BevyContext, 512>> + Send + Sync + 'static) -> Self {
+        Self {
+            factory: Arc::new(factory),
+        }
+    }
+}
+
+impl NodeComponent for EffectBus {
+    type Params = ();
+    type FadeHandle = ();
+
+    fn create_node(entity: EntityWorldMut, audio_graph: &mut AudioGraph) -> NodeID {
+        let this = entity.get::<EffectBus>().unwrap();
+        let node = (this.factory)();
+        let node_id = audio_graph.add_node(1, 2, node);
+        audio_graph
+            .connect(node_id, 0, audio_graph.graph_out_node(), 0, false)
+            .unwrap();
+        audio_graph
+            .connect(node_id, 1, audio_graph.graph_out_node(), 1, false)
+            .unwrap();
+        node_id
+    }
+
+    fn to_params(&self) -> Self::Params {}
+}
+
+/// Sends this entity's node (which must also carry a [`NodeComponent`]) into `bus`'s effect node
+/// at `level`, in addition to whatever the node is already connected to directly.
+#[derive(Debug, Clone, Component)]
+pub struct EffectSend {
+    pub bus: Entity,
+    pub level: f32,
+}
+
+/// The gain stage backing one [`EffectSend`]'s edge into its bus, so [`update_send_levels`] can
+/// reach it without touching the graph.
+#[derive(Debug, Clone, Component)]
+struct SendGain(Arc<AtomicF32>);
+
+#[derive(Debug, Clone)]
+struct SendGainNode(Arc<AtomicF32>);
+
+impl<C, const MBF: usize> AudioNode<C, MBF> for SendGainNode {
+    fn debug_name(&self) -> &'static str {
+        "effect_send"
+    }
+
+    fn info(&self) -> AudioNodeInfo {
+        AudioNodeInfo {
+            num_min_supported_inputs: 1,
+            num_max_supported_inputs: 1,
+            num_min_supported_outputs: 1,
+            num_max_supported_outputs: 1,
+        }
+    }
+
+    fn activate(
+        &mut self,
+        _sample_rate: u32,
+        _num_inputs: usize,
+        _num_outputs: usize,
+    ) -> Result<Box<dyn AudioNodeProcessor<C, MBF>>, Box<dyn Error>> {
+        Ok(Box::new(SendGainProcessor { gain: self.0.clone() }))
+    }
+}
+
+struct SendGainProcessor {
+    gain: Arc<AtomicF32>,
+}
+
+impl<C, const MBF: usize> AudioNodeProcessor<C, MBF> for SendGainProcessor {
+    fn process(
+        &mut self,
+        frames: BlockFrames<MBF>,
+        inputs: &[&[f32; MBF]],
+        outputs: &mut [&mut [f32; MBF]],
+        _proc_info: ProcInfo<C>,
+    ) {
+        let gain = self.gain.load(Ordering::Relaxed);
+        for i in 0..frames.get() {
+            outputs[0][i] = inputs[0][i] * gain;
+        }
+    }
+}
+
+/// Tracks the firewheel node id of each live send's gain stage, so [`teardown_send`] can remove
+/// just that edge without disturbing the source node or the bus.
+#[derive(Resource, Default)]
+struct SendNodes {
+    data: EntityHashMap<Entity, NodeID>,
+}
+
+/// Sends whose source node or bus node isn't ready yet (e.g. the bus entity's [`EffectBus`] was
+/// spawned the same frame, so its [`NodeId`] hasn't been inserted by [`NodePlugin`] yet); retried
+/// by [`setup_sends`] every frame until both nodes show up.
+#[derive(Resource, Default)]
+struct PendingSends(Vec<Entity>);
+
+pub struct BusPlugin;
+
+impl Plugin for BusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(NodePlugin::<EffectBus>::default())
+            .init_resource::<SendNodes>()
+            .init_resource::<PendingSends>()
+            .observe(teardown_send)
+            .add_systems(PostUpdate, (setup_sends, update_send_levels).chain());
+    }
+}
+
+fn setup_sends(
+    mut commands: Commands,
+    mut pending: ResMut<PendingSends>,
+    added: Query<Entity, Added<EffectSend>>,
+    sends: Query<&EffectSend>,
+    node_ids: Query<&NodeId>,
+) {
+    pending.0.extend(added.iter());
+    pending.0.retain(|&entity| {
+        // The entity (or just its `EffectSend`) may have gone away while this was pending.
+        let Ok(send) = sends.get(entity) else {
+            return false;
+        };
+        // Either side's `NodeId` may not exist yet — the source's own node, or the bus's, could
+        // have been spawned this same frame (or later). Try again next frame rather than
+        // panicking on a perfectly valid race.
+        let Ok(&NodeId(source_node)) = node_ids.get(entity) else {
+            return true;
+        };
+        let Ok(&NodeId(bus_node)) = node_ids.get(send.bus) else {
+            return true;
+        };
+        let level = send.level;
+        commands
+            .entity(entity)
+            .update_audio_graph(move |world, entity, audio_graph| {
+                let gain = Arc::new(AtomicF32::new(level));
+                let node: Box<dyn AudioNode<_, 512>> = Box::new(SendGainNode(gain.clone()));
+                let send_node = audio_graph.add_node(1, 1, node);
+                audio_graph.connect(source_node, 0, send_node, 0, false).unwrap();
+                // Additive: the bus input is shared by every node sending to it.
+                audio_graph.connect(send_node, 0, bus_node, 0, true).unwrap();
+                world.resource_mut::<SendNodes>().data.insert(entity, send_node);
+                world.entity_mut(entity).insert(SendGain(gain));
+            });
+        false
+    });
+}
+
+fn update_send_levels(q: Query<(&EffectSend, &SendGain), Changed<EffectSend>>) {
+    for (send, gain) in &q {
+        gain.0.store(send.level, Ordering::Relaxed);
+    }
+}
+
+fn teardown_send(trigger: Trigger<OnRemove, EffectSend>, mut commands: Commands) {
+    let entity = trigger.entity();
+    commands.add(move |world: &mut World| {
+        let Some(send_node) = world.resource_mut::<SendNodes>().data.remove(&entity) else {
+            return;
+        };
+        if let Some(mut entity_mut) = world.get_entity_mut(entity) {
+            entity_mut.remove::<SendGain>();
+        }
+        crate::apply_audio_graph_command(world, move |_world, audio_graph| {
+            audio_graph.remove_node(send_node).unwrap();
+        });
+    });
+}
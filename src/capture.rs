@@ -0,0 +1,84 @@
+//! Microphone / line-in capture as an ordinary graph source node.
+//!
+//! [`InputCapture`] doesn't carry any parameters of its own: the engine's input channels are
+//! already being fed by whatever [`crate::InputDevice`] is configured, so this node is just a
+//! passthrough that makes those channels reachable from the rest of the graph. It's deliberately
+//! left unconnected to `graph_out_node()` by [`InputCapture::create_node`] — wiring a live mic
+//! straight to the output would feed back into itself the moment anything else taps it, so users
+//! route it onward themselves (e.g. into an [`crate::bus::EffectBus`] via [`crate::bus::EffectSend`]).
+use crate::node::NodeComponent;
+use crate::AudioGraph;
+use bevy_ecs::prelude::*;
+use bevy_ecs::world::EntityWorldMut;
+use firewheel::graph::NodeID;
+use firewheel::node::{AudioNode, AudioNodeInfo, AudioNodeProcessor, ProcInfo};
+use firewheel::BlockFrames;
+use std::error::Error;
+
+/// Marker component exposing the engine's capture device as a 2-in/2-out passthrough node.
+#[derive(Debug, Default, Component)]
+pub struct InputCapture;
+
+impl NodeComponent for InputCapture {
+    type Params = ();
+    type FadeHandle = ();
+
+    fn create_node(entity: EntityWorldMut, audio_graph: &mut AudioGraph) -> NodeID {
+        let node: Box<dyn AudioNode<_, 512>> = Box::new(InputCaptureNode);
+        let node_id = audio_graph.add_node(2, 2, node);
+        audio_graph
+            .connect(audio_graph.graph_in_node(), 0, node_id, 0, false)
+            .unwrap();
+        audio_graph
+            .connect(audio_graph.graph_in_node(), 1, node_id, 1, false)
+            .unwrap();
+        let _ = entity;
+        node_id
+    }
+
+    fn to_params(&self) -> Self::Params {}
+}
+
+#[derive(Debug, Clone, Copy)]
+struct InputCaptureNode;
+
+impl<C, const MBF: usize> AudioNode<C, MBF> for InputCaptureNode {
+    fn debug_name(&self) -> &'static str {
+        "input_capture"
+    }
+
+    fn info(&self) -> AudioNodeInfo {
+        AudioNodeInfo {
+            num_min_supported_inputs: 2,
+            num_max_supported_inputs: 2,
+            num_min_supported_outputs: 2,
+            num_max_supported_outputs: 2,
+        }
+    }
+
+    fn activate(
+        &mut self,
+        _sample_rate: u32,
+        _num_inputs: usize,
+        _num_outputs: usize,
+    ) -> Result<Box<dyn AudioNodeProcessor<C, MBF>>, Box<dyn Error>> {
+        Ok(Box::new(InputCaptureProcessor))
+    }
+}
+
+struct InputCaptureProcessor;
+
+impl<C, const MBF: usize> AudioNodeProcessor<C, MBF> for InputCaptureProcessor {
+    fn process(
+        &mut self,
+        frames: BlockFrames<MBF>,
+        inputs: &[&[f32; MBF]],
+        outputs: &mut [&mut [f32; MBF]],
+        _proc_info: ProcInfo<C>,
+    ) {
+        for i in 0..frames.get() {
+            outputs[0][i] = inputs[0][i];
+            outputs[1][i] = inputs[1][i];
+        }
+    }
+}
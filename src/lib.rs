@@ -9,7 +9,12 @@ use firewheel::graph::{AudioGraph as FirewheelGraph, NodeID};
 use firewheel::{ActiveFwCpalCtx, InactiveFwCpalCtx, UpdateStatus};
 use log::error;
 
+pub mod bus;
+pub mod capture;
 pub mod node;
+pub mod param;
+pub mod sample;
+pub mod spatial;
 
 const DEFAULT_MAX_BLOCK_FRAMES: usize = 512;
 
@@ -47,7 +52,10 @@ impl Plugin for AudioPlugin {
         app.init_non_send_resource::<AudioEngineBuilder>();
         app.add_systems(Last, update_audio_engine).add_systems(
             PostUpdate,
-            update_output_device.run_if(resource_exists_and_changed::<OutputDevice>),
+            update_output_device.run_if(
+                resource_exists_and_changed::<OutputDevice>
+                    .or_else(resource_exists_and_changed::<InputDevice>),
+            ),
         );
     }
 
@@ -56,8 +64,11 @@ impl Plugin for AudioPlugin {
             .world_mut()
             .remove_non_send_resource::<AudioEngineBuilder>()
             .unwrap();
-        // let input_device = app.world().get_resource::<InputDevice>().map(|s| &s.0);
+        let input_device = app.world().get_resource::<InputDevice>().map(|s| &s.0);
         let output_device = app.world().get_resource::<OutputDevice>().map(|s| &s.0);
+        // `with_input_device` configures capture before activation; `activate`'s own 2nd
+        // positional argument is unrelated (whether to fall back to the default output device).
+        let cx = cx.with_input_device(input_device);
         let cx = cx
             .activate(output_device, true, BevyContext)
             .expect("Cannot start audio engine");
@@ -65,16 +76,20 @@ impl Plugin for AudioPlugin {
     }
 }
 
+// Also reacts to `Changed<InputDevice>` (see `AudioPlugin::build`) so switching either device
+// goes through the same deactivate/reactivate cycle, keeping whichever side didn't change intact.
 fn update_output_device(world: &mut World) {
     let AudioEngine(cx) = world
         .remove_non_send_resource()
         .expect("Audio engine incorrectly set up");
-    let OutputDevice(out_device) = world.resource();
-    info!("Changing output device to {out_device:?}");
+    let out_device = world.get_resource::<OutputDevice>().map(|d| &d.0);
+    let in_device = world.get_resource::<InputDevice>().map(|d| &d.0);
+    info!("Changing audio devices: output={out_device:?}, input={in_device:?}");
 
     let (cx, context) = cx.deactivate();
+    let cx = cx.with_input_device(in_device);
     let cx = cx
-        .activate(Some(out_device), true, context.unwrap())
+        .activate(out_device, true, context.unwrap())
         .expect("Couldn't restart audio engine");
     world.insert_non_send_resource(AudioEngine(cx));
 }
@@ -1,20 +1,103 @@
 use crate::{AudioGraph, UpdateAudioGraphExt};
-use bevy_app::{App, Plugin, PostUpdate};
+use bevy_app::{App, Last, Plugin, PostUpdate};
 use bevy_ecs::prelude::*;
-use bevy_ecs::world::EntityWorldMut;
+use bevy_ecs::world::{EntityRef, EntityWorldMut};
+use bevy_time::prelude::Time;
 use bevy_utils::EntityHashMap;
+use crossbeam_channel::{Receiver, Sender};
 use firewheel::graph::NodeID;
 use std::marker::PhantomData;
+use std::time::Duration;
 
 #[derive(Event)]
 pub struct OnChange;
 
+/// Capacity of each node's control channel. `detect_changes` sends at most one
+/// [`ControlMessage`] per entity per `PostUpdate`, and the processor drains the channel at the
+/// start of every `process` call (many times a frame); a handful of frames' worth of slack is
+/// enough to absorb scheduling jitter without letting a stalled audio thread make the Bevy side
+/// buffer an unbounded backlog of stale updates.
+const CONTROL_CHANNEL_CAPACITY: usize = 4;
+
+/// A message sent from the Bevy world to an [`AudioNodeProcessor`](firewheel::node::AudioNodeProcessor)
+/// through the control channel set up by [`NodePlugin`].
+pub enum ControlMessage<P> {
+    /// Replace the processor's parameters wholesale with the given snapshot.
+    Update(P),
+}
+
+/// The receiving end of a node's control channel, inserted as a component on the node's entity
+/// so that [`NodeComponent::create_node`] can hand it off to the [`AudioNode`](firewheel::node::AudioNode)
+/// it builds.
+#[derive(Component)]
+pub struct ControlReceiver<N: NodeComponent>(pub Receiver<ControlMessage<N::Params>>);
+
+/// How a node behaves when its [`NodeComponent`] is removed or its entity despawned.
+///
+/// Inspired by bevy_fmod's `despawn_stop_mode`: `Immediate` is a hard cut, which is fine for most
+/// nodes but can click audibly for long-running sources. `AllowFadeout` keeps the firewheel node
+/// alive a little longer so it can be silenced gracefully first.
+#[derive(Debug, Clone, Copy)]
+pub enum StopMode {
+    /// Remove the firewheel node the instant the component is removed.
+    Immediate,
+    /// Keep the node alive for `duration` seconds, calling [`NodeComponent::fade_out`] every frame
+    /// with the fade's progress, before actually removing it.
+    AllowFadeout { duration: f32 },
+}
+
+impl Default for StopMode {
+    fn default() -> Self {
+        StopMode::Immediate
+    }
+}
+
 #[allow(unused_variables)]
 pub trait NodeComponent: Component {
+    /// Snapshot of this component's data that is safe to send across the control channel, i.e. to
+    /// the audio thread. This is what lets non-atomic data (enums, strings, `Vec`s, ...) stay in
+    /// sync with the processor without each node author reinventing atomics.
+    type Params: Send + 'static;
+
+    /// Whatever [`NodeComponent::fade_out`] needs to silence the node, captured once by
+    /// [`NodeComponent::fade_handle`] while the entity still exists and then handed to every
+    /// `fade_out` call by value. Nodes that don't override [`NodeComponent::stop_mode`] to
+    /// [`StopMode::AllowFadeout`] can leave this as `()`.
+    type FadeHandle: Send + Sync + 'static;
+
     fn create_node(entity: EntityWorldMut, audio_graph: &mut AudioGraph) -> NodeID;
     fn remove_node(entity: EntityWorldMut, audio_graph: &mut AudioGraph, node_id: NodeID) {
         audio_graph.remove_node(node_id).unwrap();
     }
+
+    /// Extracts the parameters to send to the processor when this component changes.
+    fn to_params(&self) -> Self::Params;
+
+    /// How this node type should behave when removed. Defaults to [`StopMode::Immediate`].
+    fn stop_mode() -> StopMode {
+        StopMode::Immediate
+    }
+
+    /// Captures the [`FadeHandle`](NodeComponent::FadeHandle) [`fade_out`](NodeComponent::fade_out)
+    /// will need, while `entity` still exists. Called from the `OnRemove` observer that fires
+    /// [`StopMode::AllowFadeout`], which for a despawn fires before the entity is actually gone —
+    /// unlike the deferred command queue `fade_out` itself later runs from, which by then may find
+    /// the entity already despawned. Defaults to `FadeHandle::default()`; override alongside a
+    /// non-`()` `FadeHandle` to pull whatever `fade_out` will need off the entity here.
+    fn fade_handle(entity: EntityRef) -> Self::FadeHandle
+    where
+        Self::FadeHandle: Default,
+    {
+        Self::FadeHandle::default()
+    }
+
+    /// Called once per frame while this node is fading out after removal (see
+    /// [`StopMode::AllowFadeout`]), with `progress` ramping linearly from `0.0` to `1.0` over the
+    /// configured duration. The default does nothing; override it alongside a gain parameter to
+    /// actually silence the node before [`NodeComponent::remove_node`] runs. Takes the handle
+    /// [`NodeComponent::fade_handle`] captured instead of entity access, since the entity may
+    /// already be despawned by the time this runs.
+    fn fade_out(fade_handle: &Self::FadeHandle, audio_graph: &mut AudioGraph, node_id: NodeID, progress: f32) {}
 }
 
 pub struct NodePlugin<N: NodeComponent>(PhantomData<fn() -> N>);
@@ -40,30 +123,177 @@ impl<N: NodeComponent> Default for NodeIds<N> {
     }
 }
 
+/// Holds the sending end of each node entity's control channel, so [`detect_changes`] can reach it
+/// without needing mutable access to the entity itself.
+#[derive(Resource)]
+struct ControlSenders<N: 'static + NodeComponent> {
+    data: EntityHashMap<Entity, Sender<ControlMessage<N::Params>>>,
+}
+
+impl<N: NodeComponent> Default for ControlSenders<N> {
+    fn default() -> Self {
+        Self {
+            data: EntityHashMap::default(),
+        }
+    }
+}
+
+struct PendingRemoval<H> {
+    node_id: NodeID,
+    started: Duration,
+    duration: Duration,
+    fade_handle: H,
+}
+
+/// Nodes that are fading out under [`StopMode::AllowFadeout`], kept alive past their entity's
+/// despawn until [`process_fades`] finishes ramping them down.
+#[derive(Resource)]
+struct PendingRemovals<N: 'static + NodeComponent> {
+    data: EntityHashMap<Entity, PendingRemoval<N::FadeHandle>>,
+    __node: PhantomData<fn() -> N>,
+}
+
+impl<N: NodeComponent> Default for PendingRemovals<N> {
+    fn default() -> Self {
+        Self {
+            data: EntityHashMap::default(),
+            __node: PhantomData,
+        }
+    }
+}
+
 impl<N: NodeComponent + 'static> Plugin for NodePlugin<N> {
     fn build(&self, app: &mut App) {
-        app.init_resource::<NodeIds<N>>().observe(on_add_node::<N>).observe(on_remove_node::<N>).add_systems(PostUpdate, detect_changes::<N>);
+        app.init_resource::<NodeIds<N>>()
+            .init_resource::<ControlSenders<N>>()
+            .init_resource::<PendingRemovals<N>>()
+            .observe(on_add_node::<N>)
+            .observe(on_remove_node::<N>)
+            .add_systems(PostUpdate, detect_changes::<N>)
+            .add_systems(Last, process_fades::<N>);
     }
 }
 
 fn on_add_node<N: NodeComponent>(trigger: Trigger<OnAdd, N>, mut commands: Commands) {
-    commands.entity(trigger.entity()).update_audio_graph(|world, entity, audio_graph| {
-        let entity_mut = world.entity_mut(entity);
-        let node_id = N::create_node(entity_mut, audio_graph);
-        world.resource_mut::<NodeIds<N>>().data.insert(entity, node_id);
+    let entity = trigger.entity();
+    let (tx, rx) = crossbeam_channel::bounded(CONTROL_CHANNEL_CAPACITY);
+    commands.entity(entity).insert(ControlReceiver::<N>(rx));
+    commands.add(move |world: &mut World| {
+        world
+            .resource_mut::<ControlSenders<N>>()
+            .data
+            .insert(entity, tx);
     });
+    commands
+        .entity(entity)
+        .update_audio_graph(|world, entity, audio_graph| {
+            let entity_mut = world.entity_mut(entity);
+            let node_id = N::create_node(entity_mut, audio_graph);
+            world.resource_mut::<NodeIds<N>>().data.insert(entity, node_id);
+            // Exposed generically (independent of `N`) so other subsystems, e.g. `spatial` or
+            // `bus`, can look up the firewheel node backing an entity without knowing its
+            // concrete `NodeComponent` type.
+            world.entity_mut(entity).insert(crate::NodeId(node_id));
+        });
 }
 
-fn on_remove_node<N: NodeComponent>(trigger: Trigger<OnRemove, N>, mut commands: Commands) {
-    commands.entity(trigger.entity()).update_audio_graph(|world, entity, audio_graph| {
-        let node_id = world.resource_mut::<NodeIds<N>>().data.remove(&entity).unwrap();
-        let entity_mut = world.entity_mut(entity);
-        N::remove_node(entity_mut, audio_graph, node_id);
+fn on_remove_node<N: NodeComponent>(trigger: Trigger<OnRemove, N>, world: &World, mut commands: Commands) {
+    let entity = trigger.entity();
+    commands.add(move |world: &mut World| {
+        world.resource_mut::<ControlSenders<N>>().data.remove(&entity);
     });
+    match N::stop_mode() {
+        StopMode::Immediate => {
+            commands
+                .entity(entity)
+                .update_audio_graph(|world, entity, audio_graph| {
+                    let node_id = world.resource_mut::<NodeIds<N>>().data.remove(&entity).unwrap();
+                    let mut entity_mut = world.entity_mut(entity);
+                    entity_mut.remove::<crate::NodeId>();
+                    N::remove_node(entity_mut, audio_graph, node_id);
+                });
+        }
+        StopMode::AllowFadeout { duration } => {
+            // Captured now, not from the deferred command below: `OnRemove` fires before a despawn
+            // actually removes the entity, but by the time a queued command runs the entity may
+            // already be gone (that's exactly the despawn case).
+            let fade_handle = N::fade_handle(world.entity(entity));
+            commands.add(move |world: &mut World| {
+                let node_id = world.resource_mut::<NodeIds<N>>().data.remove(&entity).unwrap();
+                if let Some(mut entity_mut) = world.get_entity_mut(entity) {
+                    entity_mut.remove::<crate::NodeId>();
+                }
+                let started = world.resource::<Time>().elapsed();
+                world.resource_mut::<PendingRemovals<N>>().data.insert(
+                    entity,
+                    PendingRemoval {
+                        node_id,
+                        started,
+                        duration: Duration::from_secs_f32(duration.max(0.)),
+                        fade_handle,
+                    },
+                );
+            });
+        }
+    }
 }
 
-fn detect_changes<N: NodeComponent>(mut commands: Commands, q: Query<Entity, Changed<N>>) {
-    for entity in &q {
+/// Ramps down and then removes every node of type `N` that is fading out under
+/// [`StopMode::AllowFadeout`]. Runs in `Last` so the fade has the rest of the frame's worth of
+/// parameter changes already applied.
+fn process_fades<N: NodeComponent>(world: &mut World) {
+    let now = world.resource::<Time>().elapsed();
+    let due: Vec<(Entity, NodeID, f32)> = world
+        .resource::<PendingRemovals<N>>()
+        .data
+        .iter()
+        .map(|(&entity, removal)| {
+            let elapsed = now.saturating_sub(removal.started);
+            let progress = if removal.duration.is_zero() {
+                1.
+            } else {
+                (elapsed.as_secs_f32() / removal.duration.as_secs_f32()).min(1.)
+            };
+            (entity, removal.node_id, progress)
+        })
+        .collect();
+
+    for (entity, node_id, progress) in due {
+        let finished = progress >= 1.;
+        crate::apply_audio_graph_command(world, move |world, audio_graph| {
+            if finished {
+                match world.get_entity_mut(entity) {
+                    Some(entity_mut) => N::remove_node(entity_mut, audio_graph, node_id),
+                    // The entity itself (not just the component) was despawned; there is nothing
+                    // left to hand to `N::remove_node`, so just drop the firewheel node.
+                    None => audio_graph.remove_node(node_id).unwrap(),
+                }
+            } else {
+                // Ramps using the handle `on_remove_node` captured while the entity still existed,
+                // so unlike `N::remove_node` above this doesn't need the entity to still be there.
+                let fade_handle = &world.resource::<PendingRemovals<N>>().data[&entity].fade_handle;
+                N::fade_out(fade_handle, audio_graph, node_id, progress);
+            }
+        });
+        if finished {
+            world.resource_mut::<PendingRemovals<N>>().data.remove(&entity);
+        }
+    }
+}
+
+fn detect_changes<N: NodeComponent>(
+    mut commands: Commands,
+    senders: Res<ControlSenders<N>>,
+    q: Query<(Entity, &N), Changed<N>>,
+) {
+    for (entity, component) in &q {
+        if let Some(sender) = senders.data.get(&entity) {
+            // `try_send`, not `send`: the channel is bounded, and a `Changed<N>` detected while
+            // the processor is falling behind drops this new update rather than block the Bevy
+            // side — the older updates already queued still get applied, and the next `Update` it
+            // does manage to send carries the then-current params.
+            let _ = sender.try_send(ControlMessage::Update(component.to_params()));
+        }
         commands.trigger_targets(OnChange, entity);
     }
 }
@@ -83,11 +313,16 @@ mod tests {
     }
 
     impl NodeComponent for TestNodeComponent {
+        type Params = ();
+        type FadeHandle = ();
+
         fn create_node(mut entity: EntityWorldMut, audio_graph: &mut AudioGraph) -> NodeID {
             entity.get_mut::<Self>().unwrap().created = true;
             let node: Box<dyn AudioNode<_, 512>> = Box::new(firewheel::basic_nodes::DummyAudioNode);
             audio_graph.add_node(0, 1, node)
         }
+
+        fn to_params(&self) -> Self::Params {}
     }
 
     #[test]
@@ -133,6 +368,7 @@ mod tests {
         let mut app = App::default();
         app.world.spawn().insert(TestNodeComponent);
         app.init_resource::<NodeIds<TestNodeComponent>>();
+        app.init_resource::<ControlSenders<TestNodeComponent>>();
 
         let entity = app.world.spawn().insert(TestNodeComponent).id();
 
@@ -140,11 +376,15 @@ mod tests {
         let mut commands = Commands::new(&mut command_queue, &app.world);
         app.world.set_changed::<TestNodeComponent>(entity);
 
-        detect_changes(commands, app.world.query::<Entity, Changed<TestNodeComponent>>());
+        detect_changes(
+            commands,
+            app.world.resource::<ControlSenders<TestNodeComponent>>(),
+            app.world.query::<(Entity, &TestNodeComponent), Changed<TestNodeComponent>>(),
+        );
         command_queue.apply(&mut app.world);
 
-        // Here, you can add assertions to verify the behavior of `detect_changes`, 
+        // Here, you can add assertions to verify the behavior of `detect_changes`,
         // such as whether the `OnChange` event was triggered.
         // Assert based on your actual application event testing mechanism.
     }
-}
\ No newline at end of file
+}
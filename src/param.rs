@@ -0,0 +1,314 @@
+//! Sample-scheduled parameter automation, modeled after the Web Audio API's `AudioParam`.
+//!
+//! An [`AudioParam`] lives on the Bevy side of a node's component and is carried to the processor
+//! through the control channel set up by [`NodePlugin`](crate::node::NodePlugin), the same way any
+//! other [`NodeComponent::Params`](crate::node::NodeComponent::Params) field is. Unlike a plain
+//! `f32`, it keeps a queue of scheduled [`ParamEvent`]s so the processor can compute a smooth,
+//! per-sample value instead of snapping on every `Changed` tick.
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// A single scheduled change to an [`AudioParam`], timed in seconds against the clock the owning
+/// node was activated with.
+#[derive(Debug, Clone)]
+pub enum ParamEvent {
+    /// Jump to `value` the instant `time` is reached.
+    SetValueAtTime { value: f32, time: f64 },
+    /// Linearly interpolate from the value in effect at `start_time` (when the previous event
+    /// ended, or schedule time zero) to `value` by `time`.
+    LinearRampToValueAtTime { value: f32, start_time: f64, time: f64 },
+    /// Exponentially interpolate from the value in effect at `start_time` (when the previous event
+    /// ended, or schedule time zero) to `value` by `time`. Falls back to a step if the starting
+    /// value is not strictly positive.
+    ExponentialRampToValueAtTime { value: f32, start_time: f64, time: f64 },
+    /// Start an exponential approach towards `target` at `time`, with the given `time_constant`.
+    /// Unlike the other variants this has no end time: it stays in effect until superseded by a
+    /// later event.
+    SetTargetAtTime { target: f32, time: f64, time_constant: f64 },
+    /// Play back `values` linearly over `duration` seconds starting at `time`.
+    SetValueCurve { values: Arc<[f32]>, time: f64, duration: f64 },
+}
+
+impl ParamEvent {
+    /// The time [`AudioParam`]'s builders treat as "the schedule already reaches this far", used
+    /// to anchor whatever gets scheduled next. For the ramp variants that's their own target
+    /// `time`, matching the Web Audio API (the next event continues from where a ramp lands) —
+    /// NOT the time the ramp itself started interpolating from; see [`ParamEvent::start_time`] for
+    /// that. For `SetValueCurve` it's the end of the curve, `time + duration`.
+    fn schedule_anchor(&self) -> f64 {
+        match self {
+            ParamEvent::SetValueAtTime { time, .. }
+            | ParamEvent::LinearRampToValueAtTime { time, .. }
+            | ParamEvent::ExponentialRampToValueAtTime { time, .. }
+            | ParamEvent::SetTargetAtTime { time, .. } => *time,
+            ParamEvent::SetValueCurve { time, duration, .. } => time + duration,
+        }
+    }
+
+    /// The time at which this event starts taking effect. For the ramp variants this is the
+    /// distinct `start_time` captured when the ramp was scheduled (the prior event's end, or
+    /// schedule time zero), and for `SetValueCurve` it's its own `time`; every other variant only
+    /// has one time, so it's the same as [`ParamEvent::schedule_anchor`].
+    fn start_time(&self) -> f64 {
+        match self {
+            ParamEvent::LinearRampToValueAtTime { start_time, .. }
+            | ParamEvent::ExponentialRampToValueAtTime { start_time, .. }
+            | ParamEvent::SetValueCurve { time: start_time, .. } => *start_time,
+            _ => self.schedule_anchor(),
+        }
+    }
+
+    /// The time at which this event is fully resolved and can be pruned, or `None` if it stays in
+    /// effect indefinitely (only `SetTargetAtTime`).
+    fn end_time(&self) -> Option<f64> {
+        match self {
+            ParamEvent::SetValueAtTime { time, .. } => Some(*time),
+            ParamEvent::LinearRampToValueAtTime { time, .. } => Some(*time),
+            ParamEvent::ExponentialRampToValueAtTime { time, .. } => Some(*time),
+            ParamEvent::SetTargetAtTime { .. } => None,
+            ParamEvent::SetValueCurve { time, duration, .. } => Some(time + duration),
+        }
+    }
+
+    fn evaluate(&self, v0: f32, time: f64) -> f32 {
+        match self {
+            ParamEvent::SetValueAtTime { value, .. } => *value,
+            ParamEvent::LinearRampToValueAtTime { value, start_time: t0, time: t1 } => {
+                let t0 = *t0;
+                if *t1 <= t0 {
+                    *value
+                } else {
+                    v0 + (value - v0) * ((time - t0) / (t1 - t0)) as f32
+                }
+            }
+            ParamEvent::ExponentialRampToValueAtTime { value, start_time: t0, time: t1 } => {
+                let t0 = *t0;
+                if v0 <= 0. || *t1 <= t0 {
+                    *value
+                } else {
+                    v0 * (value / v0).powf(((time - t0) / (t1 - t0)) as f32)
+                }
+            }
+            ParamEvent::SetTargetAtTime {
+                target,
+                time: t0,
+                time_constant,
+            } => target + (v0 - target) * (-((time - t0) / time_constant) as f32).exp(),
+            ParamEvent::SetValueCurve {
+                values,
+                time: t0,
+                duration,
+            } => {
+                if values.is_empty() {
+                    return v0;
+                }
+                if values.len() == 1 {
+                    return values[0];
+                }
+                let progress = ((time - t0) / duration).clamp(0., 1.);
+                let scaled = progress * (values.len() - 1) as f64;
+                let index = scaled.floor() as usize;
+                let frac = (scaled - index as f64) as f32;
+                let a = values[index];
+                let b = values[(index + 1).min(values.len() - 1)];
+                a + (b - a) * frac
+            }
+        }
+    }
+}
+
+/// A node parameter that can be snapped to a value or automated over time with ramps and curves.
+#[derive(Debug, Clone)]
+pub struct AudioParam {
+    value: f32,
+    events: VecDeque<ParamEvent>,
+    schedule_time: f64,
+}
+
+impl AudioParam {
+    pub fn new(initial: f32) -> Self {
+        Self {
+            value: initial,
+            events: VecDeque::new(),
+            schedule_time: 0.,
+        }
+    }
+
+    /// The value that would be read right now, ignoring any scheduled automation.
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Immediately sets the value and cancels any pending automation.
+    pub fn set_value(&mut self, value: f32) {
+        self.value = value;
+        self.events.clear();
+        self.schedule_time = 0.;
+    }
+
+    /// Fast-forwards the schedule anchor to `now`, pruning any events that have fully elapsed.
+    ///
+    /// The builders below (`linear_ramp_to` and friends) always schedule relative to the anchor
+    /// left by the last pushed event, which otherwise stays at `0.` until something is scheduled.
+    /// A caller that ticks this param against a clock which doesn't itself start at `0.` (e.g. a
+    /// node's own transport time, read back from the processor) needs to call this first, or the
+    /// next ramp's target time will be computed relative to `0.` and fall in that clock's past.
+    ///
+    /// This runs [`AudioParam::tick`] to catch the anchor up to `now` first, so it keeps working
+    /// after the very first scheduled event: without that, `events` would never empty out again
+    /// and every ramp past the first would be anchored to a stale `schedule_time` instead of the
+    /// live clock, landing in the processor's past and snapping rather than gliding. Pruning here
+    /// also keeps the queue this component carries across the control channel from growing
+    /// without bound.
+    pub fn sync_schedule_time(&mut self, now: f64) {
+        self.tick(now);
+        self.schedule_time = self.schedule_time.max(now);
+    }
+
+    fn push(&mut self, event: ParamEvent) -> &mut Self {
+        self.schedule_time = event.schedule_anchor();
+        self.events.push_back(event);
+        self
+    }
+
+    pub fn set_value_at_time(&mut self, value: f32, time: f64) -> &mut Self {
+        self.push(ParamEvent::SetValueAtTime { value, time })
+    }
+
+    /// Linearly ramps to `value` over `duration` seconds, starting from whatever value is in
+    /// effect when the previously scheduled event ends (or at the current schedule anchor, `0.`
+    /// unless moved by [`AudioParam::sync_schedule_time`], if there is none).
+    pub fn linear_ramp_to(&mut self, value: f32, duration: f64) -> &mut Self {
+        let start_time = self.schedule_time;
+        let time = start_time + duration;
+        self.push(ParamEvent::LinearRampToValueAtTime { value, start_time, time })
+    }
+
+    /// Exponentially ramps to `value` over `duration` seconds. Requires the starting value to be
+    /// strictly positive; the processor falls back to a step otherwise.
+    pub fn exponential_ramp_to(&mut self, value: f32, duration: f64) -> &mut Self {
+        let start_time = self.schedule_time;
+        let time = start_time + duration;
+        self.push(ParamEvent::ExponentialRampToValueAtTime { value, start_time, time })
+    }
+
+    /// Starts an exponential approach towards `target` after `delay` seconds, with the given
+    /// `time_constant`. Stays in effect until another event is scheduled.
+    pub fn set_target(&mut self, target: f32, delay: f64, time_constant: f64) -> &mut Self {
+        let time = self.schedule_time + delay;
+        self.push(ParamEvent::SetTargetAtTime {
+            target,
+            time,
+            time_constant,
+        })
+    }
+
+    /// Plays back `values` linearly over `duration` seconds, starting after `delay` seconds.
+    pub fn set_value_curve(&mut self, values: impl Into<Arc<[f32]>>, delay: f64, duration: f64) -> &mut Self {
+        let time = self.schedule_time + delay;
+        self.push(ParamEvent::SetValueCurve {
+            values: values.into(),
+            time,
+            duration,
+        })
+    }
+
+    /// Computes the value at `time` seconds, pruning any events that have fully elapsed.
+    pub fn tick(&mut self, time: f64) -> f32 {
+        loop {
+            let should_pop = match self.events.front() {
+                Some(event) => match event.end_time() {
+                    Some(end) if end <= time => {
+                        self.value = event.evaluate(self.value, end);
+                        true
+                    }
+                    // `SetTargetAtTime` has no end of its own (it stays in effect until
+                    // superseded), so it only gets pruned once whatever comes after it actually
+                    // starts — otherwise it would sit at the front forever and block every event
+                    // scheduled behind it.
+                    None => match self.events.get(1) {
+                        Some(next) if next.start_time() <= time => {
+                            self.value = event.evaluate(self.value, next.start_time());
+                            true
+                        }
+                        _ => false,
+                    },
+                    _ => false,
+                },
+                None => false,
+            };
+            if should_pop {
+                self.events.pop_front();
+            } else {
+                break;
+            }
+        }
+        match self.events.front() {
+            Some(event) if event.start_time() <= time => event.evaluate(self.value, time),
+            _ => self.value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn immediate_value_has_no_automation() {
+        let mut param = AudioParam::new(440.);
+        assert_eq!(param.tick(0.), 440.);
+        assert_eq!(param.tick(10.), 440.);
+    }
+
+    #[test]
+    fn linear_ramp_interpolates() {
+        let mut param = AudioParam::new(0.);
+        param.linear_ramp_to(1., 2.);
+        assert_eq!(param.tick(0.), 0.);
+        assert_eq!(param.tick(1.), 0.5);
+        assert_eq!(param.tick(2.), 1.);
+        // The event is pruned once reached, so later ticks hold the final value.
+        assert_eq!(param.tick(3.), 1.);
+    }
+
+    #[test]
+    fn exponential_ramp_falls_back_to_step_from_zero() {
+        let mut param = AudioParam::new(0.);
+        param.exponential_ramp_to(1., 1.);
+        assert_eq!(param.tick(1.), 1.);
+    }
+
+    #[test]
+    fn set_target_approaches_asymptotically() {
+        let mut param = AudioParam::new(0.);
+        param.set_target(1., 0., 1.);
+        let halfway = param.tick(1.);
+        assert!(halfway > 0.5 && halfway < 1.);
+    }
+
+    #[test]
+    fn set_target_is_superseded_by_a_later_event() {
+        let mut param = AudioParam::new(0.);
+        param.set_target(1., 0., 1.);
+        param.set_value_at_time(0.5, 2.);
+
+        // Before the later event starts, the set-target is still approaching its asymptote.
+        let mid = param.tick(1.);
+        assert!(mid > 0. && mid < 1.);
+
+        // Without advancing past the set-target once a following event's start_time is reached,
+        // this would stay stuck approaching `1.` forever instead of snapping to `0.5`.
+        assert_eq!(param.tick(2.), 0.5);
+        assert_eq!(param.tick(3.), 0.5);
+    }
+
+    #[test]
+    fn value_curve_interpolates_between_samples() {
+        let mut param = AudioParam::new(0.);
+        param.set_value_curve([0., 1., 0.], 0., 1.);
+        assert_eq!(param.tick(0.), 0.);
+        assert_eq!(param.tick(0.5), 1.);
+        assert_eq!(param.tick(1.), 0.);
+    }
+}
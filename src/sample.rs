@@ -0,0 +1,337 @@
+//! Decoded-audio asset loading and sample playback, mirroring the buffer/source model used by
+//! bevy_openal and bevy_synthizer but recast onto firewheel's graph.
+//!
+//! [`Buffer`] assets are decoded once (from `.ogg`, `.flac` or `.wav`) by [`BufferAssetLoader`] and
+//! shared by `Handle<Buffer>` between any number of [`SamplePlayer`]s. Because decoding finishes on
+//! Bevy's own schedule rather than synchronously inside [`NodeComponent::create_node`], a
+//! [`SamplePlayer`] node is created right away but plays silence until [`resolve_sample_buffers`]
+//! notices the handle has loaded and hands the decoded data to the processor over the usual
+//! control channel.
+use crate::node::{ControlMessage, ControlReceiver, NodeComponent};
+use crate::{AudioGraph, UpdateAudioGraphExt};
+use bevy_app::{App, Plugin, Update};
+use bevy_asset::io::Reader;
+use bevy_asset::{Asset, AssetApp, AssetLoader, Assets, AsyncReadExt, Handle, LoadContext};
+use bevy_ecs::prelude::*;
+use bevy_ecs::world::EntityWorldMut;
+use bevy_reflect::TypePath;
+use firewheel::graph::NodeID;
+use firewheel::node::{AudioNode, AudioNodeInfo, AudioNodeProcessor, ProcInfo};
+use firewheel::BlockFrames;
+use std::error::Error as StdError;
+use std::io::Cursor;
+use std::sync::Arc;
+
+/// Decoded, interleaved PCM audio ready for playback.
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct Buffer {
+    pub samples: Arc<[f32]>,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl Buffer {
+    fn frame_count(&self) -> usize {
+        self.samples.len() / self.channels.max(1) as usize
+    }
+
+    /// Linearly-interpolated stereo frame at fractional frame index `position`, looping or
+    /// clamping to silence past the end depending on `looping`.
+    fn sample_at(&self, position: f64, looping: bool) -> Option<(f32, f32)> {
+        let frame_count = self.frame_count();
+        if frame_count == 0 {
+            return None;
+        }
+        let idx = position as usize;
+        if idx >= frame_count {
+            if looping {
+                return self.sample_at(position % frame_count as f64, looping);
+            }
+            return None;
+        }
+        let frac = (position - idx as f64) as f32;
+        let next_idx = if idx + 1 < frame_count {
+            idx + 1
+        } else if looping {
+            0
+        } else {
+            idx
+        };
+        let channels = self.channels as usize;
+        let read = |i: usize, channel: usize| self.samples[i * channels + channel.min(channels - 1)];
+        let (l0, r0) = (read(idx, 0), read(idx, channels.saturating_sub(1)));
+        let (l1, r1) = (read(next_idx, 0), read(next_idx, channels.saturating_sub(1)));
+        Some((l0 + (l1 - l0) * frac, r0 + (r1 - r0) * frac))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BufferLoadError {
+    #[error("failed to read asset: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("unrecognized or corrupt audio file")]
+    Decode,
+}
+
+/// Decodes `.ogg`, `.flac` and `.wav` files into a [`Buffer`].
+#[derive(Default)]
+pub struct BufferAssetLoader;
+
+impl AssetLoader for BufferAssetLoader {
+    type Asset = Buffer;
+    type Settings = ();
+    type Error = BufferLoadError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        match load_context.path().extension().and_then(|ext| ext.to_str()) {
+            Some("ogg") => decode_ogg(&bytes),
+            Some("flac") => decode_flac(&bytes),
+            Some("wav") => decode_wav(&bytes),
+            _ => Err(BufferLoadError::Decode),
+        }
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ogg", "flac", "wav"]
+    }
+}
+
+fn decode_ogg(bytes: &[u8]) -> Result<Buffer, BufferLoadError> {
+    let mut stream = lewton::inside_ogg::OggStreamReader::new(Cursor::new(bytes)).map_err(|_| BufferLoadError::Decode)?;
+    let channels = stream.ident_hdr.audio_channels as u16;
+    let sample_rate = stream.ident_hdr.audio_sample_rate;
+    let mut samples = Vec::new();
+    while let Some(packet) = stream.read_dec_packet_itl().map_err(|_| BufferLoadError::Decode)? {
+        samples.extend(packet.into_iter().map(|s| s as f32 / i16::MAX as f32));
+    }
+    Ok(Buffer {
+        samples: samples.into(),
+        sample_rate,
+        channels,
+    })
+}
+
+fn decode_flac(bytes: &[u8]) -> Result<Buffer, BufferLoadError> {
+    let mut reader = claxon::FlacReader::new(Cursor::new(bytes)).map_err(|_| BufferLoadError::Decode)?;
+    let info = reader.streaminfo();
+    let scale = (1i64 << (info.bits_per_sample - 1)) as f32;
+    let mut samples = Vec::new();
+    for sample in reader.samples() {
+        let sample = sample.map_err(|_| BufferLoadError::Decode)?;
+        samples.push(sample as f32 / scale);
+    }
+    Ok(Buffer {
+        samples: samples.into(),
+        sample_rate: info.sample_rate,
+        channels: info.channels as u16,
+    })
+}
+
+fn decode_wav(bytes: &[u8]) -> Result<Buffer, BufferLoadError> {
+    let mut reader = hound::WavReader::new(Cursor::new(bytes)).map_err(|_| BufferLoadError::Decode)?;
+    let spec = reader.spec();
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<Result<_, _>>()
+            .map_err(|_| BufferLoadError::Decode)?,
+        hound::SampleFormat::Int => reader
+            .samples::<i32>()
+            .map(|s| s.map(|s| s as f32 / (1i64 << (spec.bits_per_sample - 1)) as f32))
+            .collect::<Result<_, _>>()
+            .map_err(|_| BufferLoadError::Decode)?,
+    };
+    Ok(Buffer {
+        samples: samples.into(),
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+    })
+}
+
+/// Plays back a decoded [`Buffer`], resampling with linear interpolation when the buffer's sample
+/// rate differs from the engine's.
+#[derive(Debug, Clone, Component)]
+pub struct SamplePlayer {
+    pub buffer: Handle<Buffer>,
+    pub gain: f32,
+    pub pitch: f32,
+    pub looping: bool,
+    pub paused: bool,
+    resolved: Option<Arc<Buffer>>,
+}
+
+impl SamplePlayer {
+    pub fn new(buffer: Handle<Buffer>) -> Self {
+        Self {
+            buffer,
+            gain: 1.,
+            pitch: 1.,
+            looping: false,
+            paused: false,
+            resolved: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SamplePlayerParams {
+    buffer: Option<Arc<Buffer>>,
+    gain: f32,
+    pitch: f32,
+    looping: bool,
+    paused: bool,
+}
+
+impl NodeComponent for SamplePlayer {
+    type Params = SamplePlayerParams;
+    type FadeHandle = ();
+
+    fn create_node(entity: EntityWorldMut, audio_graph: &mut AudioGraph) -> NodeID {
+        let this = entity.get::<Self>().unwrap();
+        let params = this.to_params();
+        let receiver = entity.get::<ControlReceiver<Self>>().unwrap().0.clone();
+        let node: Box<dyn AudioNode<_, 512>> = Box::new(SamplePlayerNode { params, receiver });
+        let node_id = audio_graph.add_node(0, 2, node);
+        audio_graph
+            .connect(node_id, 0, audio_graph.graph_out_node(), 0, false)
+            .unwrap();
+        audio_graph
+            .connect(node_id, 1, audio_graph.graph_out_node(), 1, false)
+            .unwrap();
+        node_id
+    }
+
+    fn to_params(&self) -> Self::Params {
+        SamplePlayerParams {
+            buffer: self.resolved.clone(),
+            gain: self.gain,
+            pitch: self.pitch,
+            looping: self.looping,
+            paused: self.paused,
+        }
+    }
+}
+
+struct SamplePlayerNode {
+    params: SamplePlayerParams,
+    receiver: crossbeam_channel::Receiver<ControlMessage<SamplePlayerParams>>,
+}
+
+impl<C, const MBF: usize> AudioNode<C, MBF> for SamplePlayerNode {
+    fn debug_name(&self) -> &'static str {
+        "sample_player"
+    }
+
+    fn info(&self) -> AudioNodeInfo {
+        AudioNodeInfo {
+            num_min_supported_inputs: 0,
+            num_max_supported_inputs: 0,
+            num_min_supported_outputs: 2,
+            num_max_supported_outputs: 2,
+        }
+    }
+
+    fn activate(
+        &mut self,
+        sample_rate: u32,
+        _num_inputs: usize,
+        _num_outputs: usize,
+    ) -> Result<Box<dyn AudioNodeProcessor<C, MBF>>, Box<dyn StdError>> {
+        Ok(Box::new(SamplePlayerProcessor {
+            params: self.params.clone(),
+            receiver: self.receiver.clone(),
+            sample_rate,
+            position: 0.,
+            finished: false,
+        }))
+    }
+}
+
+struct SamplePlayerProcessor {
+    params: SamplePlayerParams,
+    receiver: crossbeam_channel::Receiver<ControlMessage<SamplePlayerParams>>,
+    sample_rate: u32,
+    position: f64,
+    finished: bool,
+}
+
+impl<C, const MBF: usize> AudioNodeProcessor<C, MBF> for SamplePlayerProcessor {
+    fn process(
+        &mut self,
+        frames: BlockFrames<MBF>,
+        _inputs: &[&[f32; MBF]],
+        outputs: &mut [&mut [f32; MBF]],
+        _proc_info: ProcInfo<C>,
+    ) {
+        while let Ok(ControlMessage::Update(params)) = self.receiver.try_recv() {
+            if !matches!((&self.params.buffer, &params.buffer), (Some(a), Some(b)) if Arc::ptr_eq(a, b)) {
+                self.position = 0.;
+                self.finished = false;
+            }
+            self.params = params;
+        }
+
+        let Some(buffer) = self.params.buffer.clone() else {
+            silence(outputs, frames);
+            return;
+        };
+        if self.params.paused || self.finished {
+            silence(outputs, frames);
+            return;
+        }
+
+        let step = self.params.pitch as f64 * (buffer.sample_rate as f64 / self.sample_rate as f64);
+        for i in 0..frames.get() {
+            match buffer.sample_at(self.position, self.params.looping) {
+                Some((l, r)) => {
+                    outputs[0][i] = l * self.params.gain;
+                    outputs[1][i] = r * self.params.gain;
+                    self.position += step;
+                }
+                None => {
+                    self.finished = true;
+                    outputs[0][i] = 0.;
+                    outputs[1][i] = 0.;
+                }
+            }
+        }
+    }
+}
+
+fn silence<const MBF: usize>(outputs: &mut [&mut [f32; MBF]], frames: BlockFrames<MBF>) {
+    for i in 0..frames.get() {
+        outputs[0][i] = 0.;
+        outputs[1][i] = 0.;
+    }
+}
+
+/// Copies newly-loaded [`Buffer`] data into its [`SamplePlayer`]s. This is a regular `Changed`
+/// write, so it flows into the processor through the same control channel as any other parameter
+/// change.
+fn resolve_sample_buffers(mut q: Query<&mut SamplePlayer>, buffers: Res<Assets<Buffer>>) {
+    for mut player in &mut q {
+        if player.resolved.is_none() {
+            if let Some(buffer) = buffers.get(&player.buffer) {
+                player.resolved = Some(Arc::new(buffer.clone()));
+            }
+        }
+    }
+}
+
+pub struct SamplePlayerPlugin;
+
+impl Plugin for SamplePlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Buffer>()
+            .init_asset_loader::<BufferAssetLoader>()
+            .add_plugins(crate::node::NodePlugin::<SamplePlayer>::default())
+            .add_systems(Update, resolve_sample_buffers);
+    }
+}
@@ -0,0 +1,211 @@
+//! Positions emitter nodes in 3D space using Bevy's own `Transform` hierarchy, recasting the
+//! emitter/listener model used by crates like bevy_synthizer and bevy_openal onto firewheel's
+//! graph and this crate's [`UpdateAudioGraphExt`] command flow.
+//!
+//! An entity with both a [`NodeComponent`](crate::node::NodeComponent) and a [`SpatialEmitter`]
+//! gets a panning/attenuation node spliced in between its source node and the graph output; a
+//! [`SpatialListener`] elsewhere in the world drives where that panner points every frame. Source
+//! nodes' `NodeComponent::create_node` (e.g. `Beep`, `SamplePlayer`) wire straight to
+//! `graph_out_node()` (there being no panner yet at that point), so [`setup_emitter_panner`] disconnects that edge itself before splicing the
+//! panner in, rather than requiring each node author to special-case emitters.
+use crate::{NodeId, UpdateAudioGraphExt};
+use atomic_float::AtomicF32;
+use bevy_app::{App, Plugin, PostUpdate};
+use bevy_ecs::prelude::*;
+use bevy_transform::prelude::GlobalTransform;
+use firewheel::node::{AudioNode, AudioNodeInfo, AudioNodeProcessor, ProcInfo};
+use firewheel::BlockFrames;
+use std::error::Error;
+use std::f32::consts::FRAC_PI_2;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// Marks the entity whose [`GlobalTransform`] spatial emitters are panned and attenuated relative
+/// to. Only the first listener found is used.
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct SpatialListener;
+
+/// Makes a [`NodeComponent`](crate::node::NodeComponent) entity audible as a point source:
+/// stereo-panned by azimuth and attenuated by distance relative to the [`SpatialListener`].
+#[derive(Debug, Clone, Copy, Component)]
+pub struct SpatialEmitter {
+    /// Distance at which the emitter plays at unity gain. Closer than this is not boosted further.
+    pub ref_distance: f32,
+    /// Distance beyond which attenuation stops increasing, or `None` for no clamp.
+    pub max_distance: Option<f32>,
+    /// Exponent applied to the inverse-distance falloff; `1.0` is the physically-correct default.
+    pub rolloff_factor: f32,
+}
+
+impl Default for SpatialEmitter {
+    fn default() -> Self {
+        Self {
+            ref_distance: 1.,
+            max_distance: None,
+            rolloff_factor: 1.,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PannerState {
+    gain_l: AtomicF32,
+    gain_r: AtomicF32,
+}
+
+/// The panner node spliced in for a [`SpatialEmitter`], kept as a component so
+/// [`update_spatial_gains`] can reach its gains without going through the audio graph.
+#[derive(Debug, Clone, Component)]
+struct SpatialPanner(Arc<PannerState>);
+
+#[derive(Debug, Clone)]
+struct PannerNode(Arc<PannerState>);
+
+impl<C, const MBF: usize> AudioNode<C, MBF> for PannerNode {
+    fn debug_name(&self) -> &'static str {
+        "spatial_panner"
+    }
+
+    fn info(&self) -> AudioNodeInfo {
+        AudioNodeInfo {
+            num_min_supported_inputs: 1,
+            num_max_supported_inputs: 1,
+            num_min_supported_outputs: 2,
+            num_max_supported_outputs: 2,
+        }
+    }
+
+    fn activate(
+        &mut self,
+        _sample_rate: u32,
+        _num_inputs: usize,
+        _num_outputs: usize,
+    ) -> Result<Box<dyn AudioNodeProcessor<C, MBF>>, Box<dyn Error>> {
+        Ok(Box::new(PannerProcessor {
+            state: self.0.clone(),
+        }))
+    }
+}
+
+struct PannerProcessor {
+    state: Arc<PannerState>,
+}
+
+impl<C, const MBF: usize> AudioNodeProcessor<C, MBF> for PannerProcessor {
+    fn process(
+        &mut self,
+        frames: BlockFrames<MBF>,
+        inputs: &[&[f32; MBF]],
+        outputs: &mut [&mut [f32; MBF]],
+        _proc_info: ProcInfo<C>,
+    ) {
+        let gain_l = self.state.gain_l.load(Ordering::Relaxed);
+        let gain_r = self.state.gain_r.load(Ordering::Relaxed);
+        for i in 0..frames.get() {
+            let sample = inputs[0][i];
+            outputs[0][i] = sample * gain_l;
+            outputs[1][i] = sample * gain_r;
+        }
+    }
+}
+
+/// Emitters whose `NodeId` wasn't ready yet (e.g. the entity's `NodeComponent` and
+/// `SpatialEmitter` were both inserted in the same command flush, so `NodePlugin`'s deferred
+/// `on_add_node` hadn't inserted `NodeId` by the time `Added<SpatialEmitter>` fired); retried by
+/// [`setup_emitter_panner`] every frame until the node shows up. Mirrors `bus.rs`'s `PendingSends`.
+#[derive(Resource, Default)]
+struct PendingEmitters(Vec<Entity>);
+
+pub struct SpatialPlugin;
+
+impl Plugin for SpatialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingEmitters>()
+            .add_systems(PostUpdate, (setup_emitter_panner, update_spatial_gains).chain());
+    }
+}
+
+fn setup_emitter_panner(
+    mut commands: Commands,
+    mut pending: ResMut<PendingEmitters>,
+    added: Query<Entity, Added<SpatialEmitter>>,
+    emitters: Query<&SpatialEmitter>,
+    node_ids: Query<&NodeId>,
+) {
+    pending.0.extend(added.iter());
+    pending.0.retain(|&entity| {
+        // The entity (or just its `SpatialEmitter`) may have gone away while this was pending.
+        if emitters.get(entity).is_err() {
+            return false;
+        }
+        // `NodeId` is inserted by a deferred command in `node::on_add_node`, so it may not
+        // exist yet even though `SpatialEmitter` already does. Try again next frame.
+        if node_ids.get(entity).is_err() {
+            return true;
+        }
+        commands
+            .entity(entity)
+            .update_audio_graph(|world, entity, audio_graph| {
+                let NodeId(source_node) = *world.entity(entity).get::<NodeId>().unwrap();
+                // Source nodes in this crate (not `InputCapture`) wire themselves in `create_node`
+                // straight to `graph_out_node()` (there being no panner yet at that point); undo that now so
+                // the dry signal doesn't keep leaking to the output alongside the panned path below.
+                // Source nodes connect their mono output to both output channels (e.g. `Beep`) or
+                // their two outputs to the matching channel (e.g. `SamplePlayer`); either edge is a
+                // no-op to disconnect if it was never there.
+                let graph_out = audio_graph.graph_out_node();
+                let _ = audio_graph.disconnect(source_node, 0, graph_out, 0);
+                let _ = audio_graph.disconnect(source_node, 0, graph_out, 1);
+                let _ = audio_graph.disconnect(source_node, 1, graph_out, 1);
+                let state = Arc::new(PannerState {
+                    gain_l: AtomicF32::new(1.),
+                    gain_r: AtomicF32::new(1.),
+                });
+                let node: Box<dyn AudioNode<_, 512>> = Box::new(PannerNode(state.clone()));
+                let panner_node = audio_graph.add_node(1, 2, node);
+                audio_graph.connect(source_node, 0, panner_node, 0, false).unwrap();
+                audio_graph
+                    .connect(panner_node, 0, audio_graph.graph_out_node(), 0, false)
+                    .unwrap();
+                audio_graph
+                    .connect(panner_node, 1, audio_graph.graph_out_node(), 1, false)
+                    .unwrap();
+                world.entity_mut(entity).insert(SpatialPanner(state));
+            });
+        false
+    });
+}
+
+fn update_spatial_gains(
+    listener: Query<&GlobalTransform, With<SpatialListener>>,
+    emitters: Query<(&GlobalTransform, &SpatialEmitter, &SpatialPanner)>,
+) {
+    let Some(listener_transform) = listener.iter().next() else {
+        return;
+    };
+    let listener_transform = listener_transform.compute_transform();
+    let listener_translation = listener_transform.translation;
+    let listener_forward = listener_transform.forward().as_vec3();
+    let listener_right = listener_transform.right().as_vec3();
+
+    for (transform, emitter, panner) in &emitters {
+        let relative = transform.translation() - listener_translation;
+        let distance = relative.length();
+
+        let azimuth = if distance > f32::EPSILON {
+            relative.dot(listener_right).atan2(relative.dot(listener_forward))
+        } else {
+            0.
+        };
+        // Equal-power pan law: map the clamped azimuth into [0, pi/2] and use cos/sin as the
+        // left/right gains.
+        let theta = (azimuth.clamp(-FRAC_PI_2, FRAC_PI_2) + FRAC_PI_2) / 2.;
+
+        let max_distance = emitter.max_distance.unwrap_or(f32::INFINITY);
+        let clamped_distance = distance.max(emitter.ref_distance).min(max_distance);
+        let distance_gain = (emitter.ref_distance / clamped_distance).powf(emitter.rolloff_factor);
+
+        panner.0.gain_l.store(theta.cos() * distance_gain, Ordering::Relaxed);
+        panner.0.gain_r.store(theta.sin() * distance_gain, Ordering::Relaxed);
+    }
+}